@@ -0,0 +1,58 @@
+//! Errors emitted by `rustc_passes`.
+
+use rustc_macros::{Diagnostic, Subdiagnostic};
+use rustc_span::symbol::Symbol;
+use rustc_span::MultiSpan;
+
+#[derive(Diagnostic)]
+#[diag(passes_required_panic_handler)]
+pub struct MissingPanicHandler {
+    #[subdiagnostic]
+    pub requiring_crate: Option<RequiringCrateNote>,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_required_alloc_error_handler)]
+pub struct MissingAllocErrorHandler {
+    #[subdiagnostic]
+    pub requiring_crate: Option<RequiringCrateNote>,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_missing_lang_item)]
+pub struct MissingLangItem {
+    pub name: Symbol,
+    #[subdiagnostic]
+    pub requiring_crate: Option<RequiringCrateNote>,
+}
+
+#[derive(Subdiagnostic)]
+pub enum RequiringCrateNote {
+    /// The local crate depends directly on the crate that declares the weak
+    /// lang item.
+    #[note(passes_required_by_crate)]
+    Direct { declaring_crate: Symbol },
+    /// The crate that declares the weak lang item was pulled in transitively,
+    /// through `puller`.
+    #[note(passes_required_by_crate_transitive)]
+    Transitive {
+        declaring_crate: Symbol,
+        puller: Symbol,
+    },
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_duplicate_weak_lang_item, code = "E0152")]
+pub struct DuplicateWeakLangItem {
+    pub name: Symbol,
+    #[primary_span]
+    pub local_definitions: MultiSpan,
+    #[subdiagnostic]
+    pub upstream_crates: Vec<AlsoDefinedInCrate>,
+}
+
+#[derive(Subdiagnostic)]
+#[note(passes_also_defined_in_crate)]
+pub struct AlsoDefinedInCrate {
+    pub crate_name: Symbol,
+}
@@ -1,14 +1,20 @@
 //! Validity checking for weak lang items
 
-use rustc_data_structures::fx::FxHashSet;
+use rustc_data_structures::fx::{FxHashMap, FxHashSet};
 use rustc_errors::struct_span_err;
+use rustc_hir::def_id::{CrateNum, LOCAL_CRATE};
 use rustc_hir::lang_items::{self, LangItem};
 use rustc_hir::weak_lang_items::WEAK_ITEMS_REFS;
 use rustc_middle::middle::lang_items::required;
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::CrateType;
+use rustc_span::symbol::Symbol;
+use rustc_span::{MultiSpan, Span};
 
-use crate::errors::{MissingAllocErrorHandler, MissingLangItem, MissingPanicHandler};
+use crate::errors::{
+    AlsoDefinedInCrate, DuplicateWeakLangItem, MissingAllocErrorHandler, MissingLangItem,
+    MissingPanicHandler, RequiringCrateNote,
+};
 
 /// Checks the crate for usage of weak lang items, returning a vector of all the
 /// language items required by this crate, but not defined yet.
@@ -45,9 +51,94 @@ pub fn check_crate<'tcx>(tcx: TyCtxt<'tcx>, items: &mut lang_items::LanguageItem
         }
     }
 
+    check_duplicates(tcx, items);
     verify(tcx, items);
 }
 
+/// A crate in which a weak lang item is defined, along with enough
+/// information to point at the definition in a diagnostic.
+enum WeakLangItemSite {
+    /// Defined in this crate, at `Span`.
+    Local(Span),
+    /// Defined in an upstream crate; weak items there carry no useful span,
+    /// so we can only point at the crate itself.
+    Upstream(CrateNum),
+}
+
+/// Checks for weak lang items that are defined more than once across the
+/// crate graph (e.g. two `#[panic_handler]`s). Unlike strong lang items,
+/// which are checked for duplicates in `rustc_middle::middle::lang_items`,
+/// weak items previously went unchecked here and only surfaced as a
+/// confusing linker error.
+fn check_duplicates<'tcx>(tcx: TyCtxt<'tcx>, items: &lang_items::LanguageItems) {
+    // Keyed by the weak lang item's name, the same way `verify` iterates
+    // `WEAK_ITEMS_REFS`. Upstream crates report `LangItem`s, which are mapped
+    // back to their names through `weak_item_names` below.
+    let mut sites: FxHashMap<Symbol, Vec<WeakLangItemSite>> = FxHashMap::default();
+
+    for (&name, &item) in WEAK_ITEMS_REFS.iter() {
+        // A real `#[panic_handler]`/`#[alloc_error_handler]` written by the
+        // user is an ordinary item, not a foreign one, so it's already
+        // recorded in `items` by the earlier lang-item collection pass; look
+        // it up there rather than rescanning `foreign_items()`, which only
+        // ever sees the `extern "Rust" { #[lang = "..."] fn ...; }`
+        // requirement stubs that `core`/`alloc` use to declare the item,
+        // never a local definition.
+        if let Some(def_id) = items.get(item) {
+            if let Some(def_id) = def_id.as_local() {
+                let span = tcx.def_span(def_id);
+                sites
+                    .entry(name)
+                    .or_default()
+                    .push(WeakLangItemSite::Local(span));
+            }
+        }
+    }
+
+    let weak_item_names: FxHashMap<LangItem, Symbol> = WEAK_ITEMS_REFS
+        .iter()
+        .map(|(&name, &item)| (item, name))
+        .collect();
+    for &cnum in tcx.crates(()).iter() {
+        for &(_, lang_item) in tcx.defined_lang_items(cnum).iter() {
+            if let Some(&name) = weak_item_names.get(&lang_item) {
+                sites
+                    .entry(name)
+                    .or_default()
+                    .push(WeakLangItemSite::Upstream(cnum));
+            }
+        }
+    }
+
+    for (&name, sites) in &sites {
+        if sites.len() <= 1 {
+            continue;
+        }
+
+        let mut local_spans = Vec::new();
+        let mut upstream_crates = Vec::new();
+        for site in sites {
+            match site {
+                WeakLangItemSite::Local(span) => local_spans.push(*span),
+                WeakLangItemSite::Upstream(cnum) => {
+                    upstream_crates.push(AlsoDefinedInCrate {
+                        crate_name: tcx.crate_name(*cnum),
+                    });
+                }
+            }
+        }
+        let mut local_definitions = MultiSpan::from_spans(local_spans.clone());
+        for span in local_spans {
+            local_definitions.push_span_label(span, "defined here");
+        }
+        tcx.sess.emit_err(DuplicateWeakLangItem {
+            name,
+            local_definitions,
+            upstream_crates,
+        });
+    }
+}
+
 fn verify<'tcx>(tcx: TyCtxt<'tcx>, items: &lang_items::LanguageItems) {
     // We only need to check for the presence of weak lang items if we're
     // emitting something that's not an rlib.
@@ -63,24 +154,66 @@ fn verify<'tcx>(tcx: TyCtxt<'tcx>, items: &lang_items::LanguageItems) {
         return;
     }
 
+    // In addition to the set of missing items, track the first crate that
+    // requires each one, so diagnostics can explain why it was pulled in.
     let mut missing = FxHashSet::default();
+    let mut first_requiring = FxHashMap::default();
     for &cnum in tcx.crates(()).iter() {
         for &item in tcx.missing_lang_items(cnum).iter() {
-            missing.insert(item);
+            if missing.insert(item) {
+                first_requiring.insert(item, cnum);
+            }
         }
     }
 
     for (name, &item) in WEAK_ITEMS_REFS.iter() {
         if missing.contains(&item) && required(tcx, item) && items.require(item).is_err() {
+            let requiring_crate = first_requiring
+                .get(&item)
+                .and_then(|&cnum| requiring_crate_note(tcx, cnum));
             if item == LangItem::PanicImpl {
-                tcx.sess.emit_err(MissingPanicHandler);
+                tcx.sess.emit_err(MissingPanicHandler { requiring_crate });
             } else if item == LangItem::Oom {
                 if !tcx.features().default_alloc_error_handler {
-                    tcx.sess.emit_err(MissingAllocErrorHandler);
+                    tcx.sess
+                        .emit_err(MissingAllocErrorHandler { requiring_crate });
                 }
             } else {
-                tcx.sess.emit_err(MissingLangItem { name: *name });
+                tcx.sess.emit_err(MissingLangItem {
+                    name: *name,
+                    requiring_crate,
+                });
+            }
+        }
+    }
+}
+
+/// Walks the dependency chain starting at `declaring_cnum` (the crate that
+/// declares the weak lang item through an `extern` lang-item stub, e.g. `core`
+/// for `panic_impl`) looking for the direct dependency of the local crate
+/// that transitively pulled it in, so the diagnostic can
+/// explain *why* the item became required instead of just *what* is missing.
+///
+/// `declaring_cnum` itself is always the crate that declares the item; it is
+/// only the same crate that "pulled it in" when the local crate depends on
+/// it directly. Both names are kept so the note never claims the puller
+/// declares the item when it's several hops away from it.
+fn requiring_crate_note(tcx: TyCtxt<'_>, declaring_cnum: CrateNum) -> Option<RequiringCrateNote> {
+    let declaring_crate = tcx.crate_name(declaring_cnum);
+    let mut puller = declaring_cnum;
+    loop {
+        let extern_crate = tcx.extern_crate(puller.as_def_id())?;
+        match extern_crate.dependency_of {
+            LOCAL_CRATE if puller == declaring_cnum => {
+                return Some(RequiringCrateNote::Direct { declaring_crate });
+            }
+            LOCAL_CRATE => {
+                return Some(RequiringCrateNote::Transitive {
+                    declaring_crate,
+                    puller: tcx.crate_name(puller),
+                });
             }
+            dep => puller = dep,
         }
     }
 }
@@ -0,0 +1,9 @@
+// A `#![no_std]` rlib that explicitly depends on `alloc`, reached only
+// through this crate. Unlike `core`, `alloc` is never auto-injected as a
+// direct dependency of the crate being compiled, so this is needed to get a
+// genuinely transitive dependency on the crate that declares the weak
+// `oom` lang item.
+#![no_std]
+#![crate_type = "rlib"]
+
+extern crate alloc;
@@ -0,0 +1,11 @@
+// A `#![no_std]` rlib that defines its own `#[panic_handler]`, for testing
+// duplicate-definition detection against a second definition elsewhere.
+#![no_std]
+#![crate_type = "rlib"]
+
+use core::panic::PanicInfo;
+
+#[panic_handler]
+fn panic(_: &PanicInfo<'_>) -> ! {
+    loop {}
+}
@@ -0,0 +1,11 @@
+// A second `#![no_std]` rlib that defines its own `#[panic_handler]`, so two
+// upstream crates can conflict with each other without either being local.
+#![no_std]
+#![crate_type = "rlib"]
+
+use core::panic::PanicInfo;
+
+#[panic_handler]
+fn panic(_: &PanicInfo<'_>) -> ! {
+    loop {}
+}
@@ -0,0 +1,16 @@
+// aux-build:panic-handler-aux.rs
+// compile-flags: -C panic=abort
+// This crate defines its own `#[panic_handler]` while also depending on
+// `panic_handler_aux`, which defines one too. The local definition should
+// get a real span; the upstream one gets a crate-level note.
+#![no_std]
+#![no_main]
+
+extern crate panic_handler_aux;
+
+use core::panic::PanicInfo;
+
+#[panic_handler]
+fn panic(_: &PanicInfo<'_>) -> ! {
+    loop {}
+}
@@ -0,0 +1,11 @@
+// aux-build:panic-handler-aux.rs
+// aux-build:panic-handler-aux2.rs
+// compile-flags: -C panic=abort
+
+// Neither `panic_handler_aux` nor `panic_handler_aux2` is local, so this
+// exercises the upstream-vs-upstream conflict with no local definition site.
+#![no_std]
+#![no_main]
+
+extern crate panic_handler_aux;
+extern crate panic_handler_aux2;
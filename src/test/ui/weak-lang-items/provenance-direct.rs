@@ -0,0 +1,7 @@
+// compile-flags: -C panic=abort
+
+// A `#![no_std]` binary that never provides a `#[panic_handler]` is missing
+// `core`'s weak `panic_impl` lang item. Since this crate depends on `core`
+// directly, the note should name `core` without a "pulled in by" clause.
+#![no_std]
+#![no_main]
@@ -0,0 +1,21 @@
+// aux-build:oom-middle.rs
+
+// This crate depends on `alloc` only transitively, through `oom_middle`.
+// `core` is auto-injected as a direct dependency of every `#![no_std]`
+// crate, which would make it a poor choice here: `alloc` isn't, so this is
+// the only way to exercise `RequiringCrateNote::Transitive` for real. The
+// missing-alloc-error-handler note must name `alloc` as the crate that
+// declares the weak lang item, and `oom_middle` as the crate that pulled it
+// in. A `#[panic_handler]` is provided so that the alloc error handler is
+// the only missing weak lang item.
+#![no_std]
+#![no_main]
+
+extern crate oom_middle;
+
+use core::panic::PanicInfo;
+
+#[panic_handler]
+fn panic(_: &PanicInfo<'_>) -> ! {
+    loop {}
+}